@@ -4,7 +4,12 @@
 //! unless otherwise noted, all indexing is done at the grapheme level.
 
 pub mod ast;
+pub mod diagnostic;
+pub mod interp;
 pub mod lexer;
+pub mod literal;
 pub mod parser;
+pub mod precedence;
 pub mod span;
 pub mod token;
+pub mod visitor;
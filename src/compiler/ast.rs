@@ -9,11 +9,12 @@ pub enum Atom {
     /// A boolean.
     Bool(Span, bool),
 
-    /// An integer.
-    Int(Span, BigInt),
+    /// An integer, with its declared type suffix if one was given (e.g. the `u8` in `255u8`).
+    Int(Span, BigInt, Option<String>),
 
-    /// A floating point number.
-    Float(Span, f64),
+    /// A floating point number, with its declared type suffix if one was given (e.g. the `f32`
+    /// in `1.0f32`).
+    Float(Span, f64, Option<String>),
 
     /// A `none`.
     None(Span),
@@ -259,6 +260,16 @@ pub enum TraitItems {
     UnaryOp(TraitAccessModifier, UnaryOp, Box<Expr>),
 }
 
+/// A part of a smart (interpolated) string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StrPart {
+    /// A literal chunk of text between interpolations.
+    Literal(Span, String),
+
+    /// An interpolated expression, written as `@{ ... }`.
+    Expr(Span, Box<Expr>),
+}
+
 /// An expression. This is really the abstract syntax tree, since an entire program is simply an
 /// expression.
 #[derive(Clone, Debug, PartialEq)]
@@ -266,6 +277,9 @@ pub enum Expr {
     /// An atomic value.
     Atom(Atom),
 
+    /// A smart (interpolated) string, broken into its literal and interpolated parts.
+    StringInterp(Vec<StrPart>),
+
     /// A name.
     Name(Name),
 
@@ -0,0 +1,357 @@
+//! Diagnostics that can be rendered against the source they describe.
+
+use super::span::Span;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The severity of a [`Diagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// An error which prevents compilation from continuing.
+    Error,
+
+    /// A warning about something that is likely a mistake, but does not prevent compilation.
+    Warning,
+
+    /// A note providing extra context, usually attached alongside an error or warning.
+    Note,
+}
+
+/// A single labeled span within a [`Diagnostic`], pointing at a piece of source code with a
+/// message describing why it is relevant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    /// The span being labeled.
+    pub span: Span,
+
+    /// The message to show beside the underline.
+    pub message: String,
+}
+
+impl Label {
+    /// Create a new [`Label`].
+    ///
+    /// # Arguments
+    ///
+    /// * `span` - The span to label.
+    /// * `message` - The message to show beside the underline.
+    ///
+    /// # Returns
+    ///
+    /// A new [`Label`].
+    pub fn new(span: Span, message: impl Into<String>) -> Label {
+        Label {
+            span: span,
+            message: message.into(),
+        }
+    }
+}
+
+/// A diagnostic message, which may point at one or more locations in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// The severity of the diagnostic.
+    pub severity: Severity,
+
+    /// The top level message of the diagnostic.
+    pub message: String,
+
+    /// The labeled spans attached to the diagnostic, in the order they should be rendered.
+    pub labels: Vec<Label>,
+}
+
+impl Diagnostic {
+    /// Create a new [`Diagnostic`] with no labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - The severity of the diagnostic.
+    /// * `message` - The top level message of the diagnostic.
+    ///
+    /// # Returns
+    ///
+    /// A new [`Diagnostic`].
+    pub fn new(severity: Severity, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity: severity,
+            message: message.into(),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Create a new error [`Diagnostic`] with no labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The top level message of the diagnostic.
+    ///
+    /// # Returns
+    ///
+    /// A new [`Diagnostic`] with [`Severity::Error`].
+    pub fn error(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Severity::Error, message)
+    }
+
+    /// Attach a labeled span to this diagnostic.
+    ///
+    /// # Arguments
+    ///
+    /// * `span` - The span to label.
+    /// * `message` - The message to show beside the underline.
+    ///
+    /// # Returns
+    ///
+    /// `self`, so labels can be chained.
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.labels.push(Label::new(span, message));
+        self
+    }
+}
+
+/// An index of the grapheme offset of each line start in a source, used to convert a [`Span`]
+/// offset into a `(line, column)` pair without rescanning the source on every lookup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineIndex {
+    /// The grapheme offset of the start of each line. The first entry is always `0`.
+    line_starts: Vec<usize>,
+
+    /// The number of graphemes in the source.
+    len: usize,
+}
+
+impl LineIndex {
+    /// Scan a source once, recording the grapheme offset of each line start.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source to index.
+    ///
+    /// # Returns
+    ///
+    /// A new [`LineIndex`] for `source`.
+    pub fn new(source: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        let mut offset = 0;
+        for g in source.graphemes(true) {
+            offset += 1;
+            if g == "\n" || g == "\r\n" || g == "\r" {
+                line_starts.push(offset);
+            }
+        }
+        LineIndex {
+            line_starts: line_starts,
+            len: offset,
+        }
+    }
+
+    /// Convert a grapheme offset into a `(line, column)` pair, both zero-indexed, by binary
+    /// searching the recorded line starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The grapheme offset to convert.
+    ///
+    /// # Returns
+    ///
+    /// The zero-indexed `(line, column)` for `offset`.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        (line, offset - self.line_starts[line])
+    }
+
+    /// Returns the grapheme offset of the start of `line`, or the length of the source if `line`
+    /// is past the last line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The zero-indexed line.
+    ///
+    /// # Returns
+    ///
+    /// The grapheme offset of the start of `line`.
+    pub fn line_start(&self, line: usize) -> usize {
+        *self.line_starts.get(line).unwrap_or(&self.len)
+    }
+
+    /// Returns the grapheme offset of the end of `line`, exclusive of its line terminator.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The zero-indexed line.
+    ///
+    /// # Returns
+    ///
+    /// The grapheme offset of the end of `line`.
+    pub fn line_end(&self, line: usize) -> usize {
+        match self.line_starts.get(line + 1) {
+            // A real next line start exists, so the line terminator sits just before it.
+            Some(&next_start) => next_start.saturating_sub(1).max(self.line_start(line)),
+            // There is no next line, so the line runs to the end of the source with no
+            // terminator to trim.
+            None => self.len,
+        }
+    }
+}
+
+/// Renders [`Diagnostic`]s against their source, printing the offending line(s) with a caret
+/// underline beneath each label, similar to modern compiler output.
+pub struct Renderer<'a> {
+    /// The source being rendered against.
+    source: &'a str,
+
+    /// The graphemes of the source, used to slice out line text by grapheme offset.
+    graphemes: Vec<&'a str>,
+
+    /// The line index for the source.
+    lines: LineIndex,
+}
+
+impl<'a> Renderer<'a> {
+    /// Create a new [`Renderer`] for some source.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source that diagnostics will be rendered against.
+    ///
+    /// # Returns
+    ///
+    /// A new [`Renderer`].
+    pub fn new(source: &'a str) -> Renderer<'a> {
+        Renderer {
+            source: source,
+            graphemes: source.graphemes(true).collect(),
+            lines: LineIndex::new(source),
+        }
+    }
+
+    /// Get the source string that the renderer is working on.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Render a [`Diagnostic`] to a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostic` - The diagnostic to render.
+    ///
+    /// # Returns
+    ///
+    /// The rendered diagnostic, including the source snippet and caret underlines for every
+    /// label.
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "{}: {}\n",
+            severity_str(diagnostic.severity),
+            diagnostic.message
+        ));
+        for label in &diagnostic.labels {
+            out.push_str(&self.render_label(label));
+        }
+        out
+    }
+
+    /// Render a single label: the source line it falls on, followed by a caret underline.
+    fn render_label(&self, label: &Label) -> String {
+        let (start_line, start_col) = self.lines.line_col(label.span.start);
+        let line_start = self.lines.line_start(start_line);
+        let line_end = self.lines.line_end(start_line);
+
+        // Clamp the end column to the line when the span crosses multiple lines.
+        let end_col = if label.span.end > line_end {
+            line_end - line_start
+        } else {
+            label.span.end - line_start
+        };
+
+        let line_text: String = self.graphemes[line_start..line_end].concat();
+        let underline: String = " ".repeat(start_col) + &"^".repeat((end_col - start_col).max(1));
+
+        format!("{}\n{} {}\n", line_text, underline, label.message)
+    }
+}
+
+fn severity_str(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_index_single_line() {
+        let index = LineIndex::new("hello");
+        assert_eq!(index.line_col(0), (0, 0));
+        assert_eq!(index.line_col(3), (0, 3));
+    }
+
+    #[test]
+    fn test_line_index_multiple_lines() {
+        let index = LineIndex::new("ab\ncd\nef");
+        assert_eq!(index.line_col(0), (0, 0));
+        assert_eq!(index.line_col(3), (1, 0));
+        assert_eq!(index.line_col(4), (1, 1));
+        assert_eq!(index.line_col(6), (2, 0));
+    }
+
+    #[test]
+    fn test_line_index_crlf() {
+        // "\r\n" is a single grapheme cluster, so it occupies one grapheme offset.
+        let index = LineIndex::new("ab\r\ncd");
+        assert_eq!(index.line_col(3), (1, 0));
+    }
+
+    #[test]
+    fn test_line_index_no_trailing_newline() {
+        let index = LineIndex::new("hello");
+        assert_eq!(index.line_end(0), 5);
+    }
+
+    #[test]
+    fn test_line_index_counts_grapheme_clusters() {
+        // "e\u{0301}" is a single grapheme cluster (two scalars), so it must count as one
+        // grapheme when locating the second line.
+        let index = LineIndex::new("e\u{0301}\nab");
+        assert_eq!(index.line_col(2), (1, 0));
+    }
+
+    #[test]
+    fn test_renderer_points_at_span() {
+        let renderer = Renderer::new("let x = 1\n");
+        let diagnostic = Diagnostic::error("unexpected token")
+            .with_label(Span::new(4, 5), "here");
+        let rendered = renderer.render(&diagnostic);
+        assert!(rendered.contains("let x = 1"));
+        assert!(rendered.contains("^"));
+        assert!(rendered.contains("here"));
+        // The underline should appear on exactly one line, beside the message, not twice.
+        assert_eq!(rendered.matches('^').count(), 1);
+    }
+
+    #[test]
+    fn test_renderer_with_no_trailing_newline() {
+        let renderer = Renderer::new("hello");
+        let diagnostic = Diagnostic::error("x").with_label(Span::new(4, 5), "here");
+        let rendered = renderer.render(&diagnostic);
+        assert!(rendered.contains("hello"));
+    }
+
+    #[test]
+    fn test_renderer_supports_multiple_labels() {
+        let renderer = Renderer::new("a + b\n");
+        let diagnostic = Diagnostic::error("mismatched operand")
+            .with_label(Span::new(2, 3), "operator")
+            .with_label(Span::new(4, 5), "operand");
+        let rendered = renderer.render(&diagnostic);
+        assert!(rendered.contains("operator"));
+        assert!(rendered.contains("operand"));
+    }
+}
@@ -0,0 +1,362 @@
+//! Lexer support for splitting a smart (interpolated) string into literal and expression parts.
+//!
+//! A smart string already produces a `StringSmart` token as one flat string; this module is what
+//! turns its inner text into the structured [`StrPart`]s that `Expr::StringInterp` is built from.
+//! On encountering `@{` the scanner switches into expression-lexing mode, balances nested `{`/`}`
+//! (including a nested `@{`) until the matching `}`, then resumes literal scanning.
+
+use super::ast::{Expr, StrPart};
+use super::parser::{ParseError, ParseErrorKind, Parser};
+use super::span::Span;
+use super::token::{Token, TokenKind};
+use crate::util::text;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Split the inner text of a smart string (with the surrounding quotes already removed) into its
+/// literal and interpolated parts.
+///
+/// # Arguments
+///
+/// * `text` - The inner text of the smart string, not including the surrounding quotes.
+/// * `base_offset` - The grapheme offset of the start of `text` within the source, so that the
+///   returned spans are absolute rather than relative to the string.
+///
+/// # Returns
+///
+/// The parsed [`StrPart`]s, in order, or a [`ParseError`] if an interpolation is unterminated or
+/// its contents do not form a valid expression.
+pub fn parse_smart_string(text: &str, base_offset: usize) -> Result<Vec<StrPart>, ParseError> {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+
+    while i < graphemes.len() {
+        if graphemes[i] == "@" && graphemes.get(i + 1) == Some(&"{") {
+            if !literal.is_empty() {
+                parts.push(StrPart::Literal(
+                    Span::new(base_offset + literal_start, base_offset + i),
+                    literal.clone(),
+                ));
+                literal.clear();
+            }
+
+            // Balance nested braces (including a nested `@{`, whose own `{` is counted the same
+            // as any other) to find the matching `}`.
+            let expr_start = i + 2;
+            let j = match find_interpolation_end(&graphemes, expr_start) {
+                Some(j) => j,
+                None => {
+                    return Err(ParseError {
+                        kind: ParseErrorKind::UnexpectedEnd,
+                        span: Span::new(base_offset + i, base_offset + graphemes.len()),
+                    });
+                }
+            };
+
+            let inner: String = graphemes[expr_start..j].concat();
+            let span = Span::new(base_offset + expr_start, base_offset + j);
+            let expr = parse_expr_fragment(&inner, base_offset + expr_start)?;
+            parts.push(StrPart::Expr(span, Box::new(expr)));
+
+            i = j + 1;
+            literal_start = i;
+        } else {
+            literal.push_str(graphemes[i]);
+            i += 1;
+        }
+    }
+
+    if !literal.is_empty() || parts.is_empty() {
+        parts.push(StrPart::Literal(
+            Span::new(base_offset + literal_start, base_offset + graphemes.len()),
+            literal,
+        ));
+    }
+
+    Ok(parts)
+}
+
+/// Find the grapheme index of the `}` that closes an interpolation, given the index just past its
+/// opening `@{`. Every `{` (including one belonging to a nested `@{`) increases the depth and
+/// every `}` decreases it, so the interpolation ends only once the depth returns to zero.
+///
+/// # Arguments
+///
+/// * `graphemes` - The full text being scanned, as grapheme clusters.
+/// * `expr_start` - The index just past the opening `@{`.
+///
+/// # Returns
+///
+/// The index of the matching `}`, or `None` if the text ends before the braces balance.
+fn find_interpolation_end(graphemes: &[&str], expr_start: usize) -> Option<usize> {
+    let mut depth = 1;
+    let mut j = expr_start;
+    while j < graphemes.len() && depth > 0 {
+        match graphemes[j] {
+            "{" => depth += 1,
+            "}" => depth -= 1,
+            _ => {}
+        }
+        if depth > 0 {
+            j += 1;
+        }
+    }
+    if depth == 0 {
+        Some(j)
+    } else {
+        None
+    }
+}
+
+/// Lex and parse the contents of a single `@{ ... }` interpolation.
+fn parse_expr_fragment(inner: &str, base_offset: usize) -> Result<Expr, ParseError> {
+    let tokens = tokenize_fragment(inner, base_offset)?;
+    Parser::new(&tokens).parse()
+}
+
+/// A minimal tokenizer for interpolation contents: names, decimal integers and floats, and the
+/// operator and grouping tokens, skipping spaces and tabs. String literals and `@`/`@@` field
+/// names are not recognized, so an interpolation containing one is rejected with
+/// `UnexpectedToken`.
+fn tokenize_fragment<'a>(src: &'a str, base_offset: usize) -> Result<Vec<Token<'a>>, ParseError> {
+    let graphemes: Vec<(usize, &'a str)> = src.grapheme_indices(true).collect();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    let mut pos = 0;
+
+    let byte_at = |i: usize| -> usize { graphemes.get(i).map(|(b, _)| *b).unwrap_or(src.len()) };
+
+    while idx < graphemes.len() {
+        let (byte_start, g) = graphemes[idx];
+
+        if g == " " || g == "\t" {
+            idx += 1;
+            pos += 1;
+            continue;
+        }
+
+        if let Some(&(_, g2)) = graphemes.get(idx + 1) {
+            let two = match (g, g2) {
+                ("&", "&") => Some(TokenKind::AmpAmp),
+                ("!", "=") => Some(TokenKind::BangEq),
+                (":", ":") => Some(TokenKind::ColonColon),
+                ("=", "=") => Some(TokenKind::EqEq),
+                (">", "=") => Some(TokenKind::GtEq),
+                (">", ">") => Some(TokenKind::GtGt),
+                ("<", "=") => Some(TokenKind::LtEq),
+                ("<", "<") => Some(TokenKind::LtLt),
+                ("|", "|") => Some(TokenKind::PipePipe),
+                ("?", "=") => Some(TokenKind::QuestionEq),
+                ("/", "/") => Some(TokenKind::SlashSlash),
+                ("*", "*") => Some(TokenKind::StarStar),
+                _ => None,
+            };
+            if let Some(kind) = two {
+                tokens.push(Token::new(
+                    &src[byte_start..byte_at(idx + 2)],
+                    kind,
+                    Span::new(base_offset + pos, base_offset + pos + 2),
+                ));
+                idx += 2;
+                pos += 2;
+                continue;
+            }
+        }
+
+        let one = match g {
+            "&" => Some(TokenKind::Amp),
+            "^" => Some(TokenKind::Caret),
+            ":" => Some(TokenKind::Colon),
+            "," => Some(TokenKind::Comma),
+            "." => Some(TokenKind::Dot),
+            "=" => Some(TokenKind::Eq),
+            ">" => Some(TokenKind::Gt),
+            "<" => Some(TokenKind::Lt),
+            "-" => Some(TokenKind::Minus),
+            "(" => Some(TokenKind::ParenL),
+            ")" => Some(TokenKind::ParenR),
+            "%" => Some(TokenKind::Percent),
+            "|" => Some(TokenKind::Pipe),
+            "+" => Some(TokenKind::Plus),
+            "?" => Some(TokenKind::Question),
+            "/" => Some(TokenKind::Slash),
+            "*" => Some(TokenKind::Star),
+            "~" => Some(TokenKind::Tilde),
+            "!" => Some(TokenKind::Bang),
+            _ => None,
+        };
+        if let Some(kind) = one {
+            tokens.push(Token::new(
+                &src[byte_start..byte_at(idx + 1)],
+                kind,
+                Span::new(base_offset + pos, base_offset + pos + 1),
+            ));
+            idx += 1;
+            pos += 1;
+            continue;
+        }
+
+        if text::is_digit(g) {
+            let mut end_idx = idx;
+            while end_idx < graphemes.len() && text::is_digit(graphemes[end_idx].1) {
+                end_idx += 1;
+            }
+
+            // A `.` followed by another digit extends this into a float literal; a bare `.`
+            // (e.g. a following method call) is left for the next iteration to lex as `Dot`.
+            let mut kind = TokenKind::IntDec;
+            if graphemes.get(end_idx).map(|&(_, g)| g) == Some(".")
+                && graphemes
+                    .get(end_idx + 1)
+                    .map_or(false, |&(_, g)| text::is_digit(g))
+            {
+                kind = TokenKind::Float;
+                end_idx += 1;
+                while end_idx < graphemes.len() && text::is_digit(graphemes[end_idx].1) {
+                    end_idx += 1;
+                }
+            }
+
+            let len = end_idx - idx;
+            tokens.push(Token::new(
+                &src[byte_start..byte_at(end_idx)],
+                kind,
+                Span::new(base_offset + pos, base_offset + pos + len),
+            ));
+            pos += len;
+            idx = end_idx;
+            continue;
+        }
+
+        if text::is_id_start(g) {
+            let mut end_idx = idx + 1;
+            while end_idx < graphemes.len() && text::is_id_continue(graphemes[end_idx].1) {
+                end_idx += 1;
+            }
+            let len = end_idx - idx;
+            let name_text = &src[byte_start..byte_at(end_idx)];
+            let kind = match text::name_case(name_text) {
+                Some(text::NameCase::Upper) => TokenKind::NameUpper,
+                None if name_text == "_" => TokenKind::NameUnderscore,
+                // Names with no cased first character (e.g. `__`, `_1`, or a caseless script)
+                // are lexed as lowercase names.
+                _ => TokenKind::NameLower,
+            };
+            tokens.push(Token::new(
+                name_text,
+                kind,
+                Span::new(base_offset + pos, base_offset + pos + len),
+            ));
+            pos += len;
+            idx = end_idx;
+            continue;
+        }
+
+        return Err(ParseError {
+            kind: ParseErrorKind::UnexpectedToken,
+            span: Span::new(base_offset + pos, base_offset + pos + 1),
+        });
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::{Atom, BinaryOp, Name};
+
+    #[test]
+    fn test_parse_smart_string_with_no_interpolation() {
+        let parts = parse_smart_string("hello", 0).unwrap();
+        assert_eq!(parts, vec![StrPart::Literal(Span::new(0, 5), "hello".into())]);
+    }
+
+    #[test]
+    fn test_parse_smart_string_with_one_interpolation() {
+        let parts = parse_smart_string("hi @{name}!", 0).unwrap();
+        match parts.as_slice() {
+            [StrPart::Literal(span_a, a), StrPart::Expr(_, expr), StrPart::Literal(span_b, b)] => {
+                assert_eq!(span_a, &Span::new(0, 3));
+                assert_eq!(a, "hi ");
+                assert_eq!(
+                    **expr,
+                    Expr::Name(Name::Lower(Span::new(5, 9), "name".into()))
+                );
+                assert_eq!(span_b, &Span::new(10, 11));
+                assert_eq!(b, "!");
+            }
+            _ => panic!("expected literal, expr, literal"),
+        }
+    }
+
+    #[test]
+    fn test_parse_smart_string_with_float_literal() {
+        let parts = parse_smart_string("@{1.5}", 0).unwrap();
+        match parts.as_slice() {
+            [StrPart::Expr(_, expr)] => {
+                assert!(matches!(**expr, Expr::Atom(Atom::Float(_, v, _)) if v == 1.5));
+            }
+            _ => panic!("expected a single interpolated expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_smart_string_with_binary_expr() {
+        let parts = parse_smart_string("@{a + b}", 0).unwrap();
+        match parts.as_slice() {
+            [StrPart::Expr(_, expr)] => {
+                assert!(matches!(**expr, Expr::BinaryOp(_, BinaryOp::Plus(_), _)));
+            }
+            _ => panic!("expected a single interpolated expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_smart_string_with_empty_interpolation_is_an_error() {
+        let err = parse_smart_string("@{}", 0).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEnd);
+    }
+
+    #[test]
+    fn test_find_interpolation_end_balances_a_nested_brace() {
+        // "{ x } }" - the inner `{`/`}` pair must not be mistaken for the closing brace.
+        let graphemes: Vec<&str> = "{ x } }".graphemes(true).collect();
+        // Start just past this interpolation's own opening `@{`, at the inner `{`.
+        assert_eq!(find_interpolation_end(&graphemes, 0), Some(6));
+    }
+
+    #[test]
+    fn test_find_interpolation_end_balances_a_nested_at_brace() {
+        // "a @{ b } }" - a nested `@{` contributes its `{` to the depth just like a plain one.
+        let graphemes: Vec<&str> = "a @{ b } }".graphemes(true).collect();
+        assert_eq!(find_interpolation_end(&graphemes, 0), Some(9));
+    }
+
+    #[test]
+    fn test_find_interpolation_end_unterminated_returns_none() {
+        let graphemes: Vec<&str> = "a + b".graphemes(true).collect();
+        assert_eq!(find_interpolation_end(&graphemes, 0), None);
+    }
+
+    #[test]
+    fn test_parse_smart_string_with_unterminated_interpolation_is_an_error() {
+        let err = parse_smart_string("hi @{name", 0).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEnd);
+    }
+
+    #[test]
+    fn test_parse_smart_string_spans_are_grapheme_indexed() {
+        // "e\u{0301}" is a single grapheme cluster (two scalars), so it is one grapheme wide.
+        let parts = parse_smart_string("e\u{0301}@{x}", 0).unwrap();
+        match parts.as_slice() {
+            [StrPart::Literal(span, text), StrPart::Expr(_, _)] => {
+                assert_eq!(span, &Span::new(0, 1));
+                assert_eq!(text, "e\u{0301}");
+            }
+            _ => panic!("expected literal, expr"),
+        }
+    }
+}
@@ -170,6 +170,7 @@ impl<'a> LexerBase<'a> {
             text: self.text(),
             kind: kind,
             span: self.span(),
+            suffix: None,
         }))
     }
 
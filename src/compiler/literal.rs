@@ -0,0 +1,175 @@
+//! Parsing of numeric literal tokens into typed values, with support for the trailing type
+//! suffixes a real language needs, e.g. `255u8`, `1.0f32`, `10i64`.
+//!
+//! This only handles the parsing side: a [`Token`]'s `suffix` must already be populated by the
+//! lexer that produced it. No lexer does that yet (`LexerEdition1::next_token` is still
+//! `unimplemented!()`), so until edition 1 lexing exists, suffixes only ever reach this module via
+//! [`Token::with_suffix`] built by hand, such as in this module's own tests.
+
+use super::diagnostic::Diagnostic;
+use super::token::Token;
+use num_bigint::BigInt;
+
+/// The suffixes accepted on an integer literal.
+const INT_SUFFIXES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128",
+];
+
+/// The suffixes accepted on a float literal.
+const FLOAT_SUFFIXES: &[&str] = &["f32", "f64"];
+
+/// The radix of an integer literal, matching the lexer's
+/// `IntBin`/`IntOct`/`IntDec`/`IntHex` token kinds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Radix {
+    /// Binary, written with a `0b` prefix.
+    Bin,
+
+    /// Octal, written with a `0o` prefix.
+    Oct,
+
+    /// Decimal, written with no prefix.
+    Dec,
+
+    /// Hexadecimal, written with a `0x` prefix.
+    Hex,
+}
+
+impl Radix {
+    /// Returns the numeric base of this radix.
+    fn base(self) -> u32 {
+        match self {
+            Radix::Bin => 2,
+            Radix::Oct => 8,
+            Radix::Dec => 10,
+            Radix::Hex => 16,
+        }
+    }
+
+    /// Returns the prefix that introduces this radix, which is empty for decimal.
+    fn prefix(self) -> &'static str {
+        match self {
+            Radix::Bin => "0b",
+            Radix::Oct => "0o",
+            Radix::Dec => "",
+            Radix::Hex => "0x",
+        }
+    }
+
+    /// Returns a human-readable name, used in diagnostic messages.
+    fn name(self) -> &'static str {
+        match self {
+            Radix::Bin => "binary",
+            Radix::Oct => "octal",
+            Radix::Dec => "decimal",
+            Radix::Hex => "hexadecimal",
+        }
+    }
+}
+
+/// Parse an integer literal token into its magnitude and declared suffix.
+///
+/// # Arguments
+///
+/// * `token` - The numeric literal token. Its `text` holds the digits (including the radix
+///   prefix, if any) and its `suffix` holds the already-lexed type suffix.
+/// * `radix` - The radix the digits are written in.
+///
+/// # Returns
+///
+/// The parsed magnitude and suffix, or a [`Diagnostic`] if the digits or the suffix are invalid.
+pub fn parse_int_literal(token: &Token, radix: Radix) -> Result<(BigInt, Option<String>), Diagnostic> {
+    let digits = token.text.strip_prefix(radix.prefix()).unwrap_or(token.text);
+    let cleaned: String = digits.chars().filter(|&c| c != '_').collect();
+
+    let value = BigInt::parse_bytes(cleaned.as_bytes(), radix.base()).ok_or_else(|| {
+        Diagnostic::error(format!("invalid {} digits in `{}`", radix.name(), token.text))
+            .with_label(token.span.clone(), "here")
+    })?;
+
+    let suffix = validate_suffix(token, INT_SUFFIXES)?;
+    Ok((value, suffix))
+}
+
+/// Parse a float literal token into its magnitude and declared suffix.
+///
+/// # Arguments
+///
+/// * `token` - The numeric literal token. Its `text` holds the digits and its `suffix` holds the
+///   already-lexed type suffix.
+///
+/// # Returns
+///
+/// The parsed magnitude and suffix, or a [`Diagnostic`] if the digits or the suffix are invalid.
+pub fn parse_float_literal(token: &Token) -> Result<(f64, Option<String>), Diagnostic> {
+    let cleaned: String = token.text.chars().filter(|&c| c != '_').collect();
+
+    let value = cleaned.parse::<f64>().map_err(|_| {
+        Diagnostic::error(format!("invalid float literal `{}`", token.text))
+            .with_label(token.span.clone(), "here")
+    })?;
+
+    let suffix = validate_suffix(token, FLOAT_SUFFIXES)?;
+    Ok((value, suffix))
+}
+
+/// Validate a token's suffix against an allowed set, returning a [`Diagnostic`] rather than
+/// panicking when the suffix is unknown.
+fn validate_suffix(token: &Token, allowed: &[&str]) -> Result<Option<String>, Diagnostic> {
+    match token.suffix {
+        None => Ok(None),
+        Some(suffix) if allowed.contains(&suffix) => Ok(Some(suffix.to_owned())),
+        Some(suffix) => Err(Diagnostic::error(format!("unknown literal suffix `{}`", suffix))
+            .with_label(token.span.clone(), "unknown suffix")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::span::Span;
+    use crate::compiler::token::TokenKind;
+
+    #[test]
+    fn test_parse_int_literal_strips_prefix_and_separators() {
+        let token = Token::new("0xFF_00", TokenKind::IntHex, Span::new(0, 7));
+        let (value, suffix) = parse_int_literal(&token, Radix::Hex).unwrap();
+        assert_eq!(value, BigInt::from(0xFF00u32));
+        assert_eq!(suffix, None);
+    }
+
+    #[test]
+    fn test_parse_int_literal_with_valid_suffix() {
+        let token = Token::with_suffix("255", TokenKind::IntDec, Span::new(0, 5), "u8");
+        let (value, suffix) = parse_int_literal(&token, Radix::Dec).unwrap();
+        assert_eq!(value, BigInt::from(255));
+        assert_eq!(suffix, Some("u8".to_owned()));
+    }
+
+    #[test]
+    fn test_parse_int_literal_with_unknown_suffix_is_a_diagnostic() {
+        let token = Token::with_suffix("255", TokenKind::IntDec, Span::new(0, 5), "ux");
+        let err = parse_int_literal(&token, Radix::Dec).unwrap_err();
+        assert!(err.message.contains("ux"));
+    }
+
+    #[test]
+    fn test_parse_int_literal_with_invalid_digits_is_a_diagnostic() {
+        let token = Token::new("0b23", TokenKind::IntBin, Span::new(0, 4));
+        assert!(parse_int_literal(&token, Radix::Bin).is_err());
+    }
+
+    #[test]
+    fn test_parse_float_literal_with_valid_suffix() {
+        let token = Token::with_suffix("1.0", TokenKind::Float, Span::new(0, 6), "f32");
+        let (value, suffix) = parse_float_literal(&token).unwrap();
+        assert_eq!(value, 1.0);
+        assert_eq!(suffix, Some("f32".to_owned()));
+    }
+
+    #[test]
+    fn test_parse_float_literal_with_unknown_suffix_is_a_diagnostic() {
+        let token = Token::with_suffix("1.0", TokenKind::Float, Span::new(0, 6), "f16");
+        assert!(parse_float_literal(&token).is_err());
+    }
+}
@@ -0,0 +1,431 @@
+//! A parser which turns a flat token stream into an [`Expr`] tree, using [`precedence`] to decide
+//! how operators are grouped.
+
+use super::ast::{Atom, BinaryOp, Expr, Name, StrPart, UnaryOp};
+use super::interp;
+use super::literal::{self, Radix};
+use super::precedence::{self, Associativity};
+use super::span::Span;
+use super::token::{Token, TokenKind};
+
+/// The kind of error that can occur while parsing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseErrorKind {
+    /// A token was encountered where it is not valid, such as one that cannot start an
+    /// expression, a missing closing `)`, an invalid literal, or a token left over after the
+    /// expression.
+    UnexpectedToken,
+
+    /// The token stream ended while an expression was still expected.
+    UnexpectedEnd,
+}
+
+/// An error produced while parsing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// The kind of error.
+    pub kind: ParseErrorKind,
+
+    /// The location of the error.
+    pub span: Span,
+}
+
+/// A precedence-climbing parser over a slice of [`Token`]s.
+pub struct Parser<'a> {
+    /// The tokens being parsed.
+    tokens: &'a [Token<'a>],
+
+    /// The index of the next token to consider, measured in tokens (not graphemes).
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Create a new [`Parser`] over some tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - The tokens to parse. Trivia tokens (space, newlines, comments) are skipped
+    ///   automatically.
+    ///
+    /// # Returns
+    ///
+    /// A new [`Parser`].
+    pub fn new(tokens: &'a [Token<'a>]) -> Parser<'a> {
+        Parser { tokens: tokens, pos: 0 }
+    }
+
+    /// Parse a single top-level expression.
+    ///
+    /// # Returns
+    ///
+    /// The parsed [`Expr`], or a [`ParseError`] if the tokens do not form a valid expression.
+    pub fn parse(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.parse_expr(precedence::min_precedence())?;
+        match self.peek() {
+            Some(tok) => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                span: tok.span.clone(),
+            }),
+            None => Ok(expr),
+        }
+    }
+
+    /// Parse an expression whose operators all bind at least as tightly as `min_prec`. This is
+    /// the core precedence-climbing routine: a prefix form is parsed as the initial `lhs`, then
+    /// binary operators are folded in for as long as their precedence satisfies `min_prec`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_prec` - The minimum precedence a binary operator must have to be consumed here.
+    ///
+    /// # Returns
+    ///
+    /// The parsed [`Expr`], or a [`ParseError`].
+    pub fn parse_expr(&mut self, min_prec: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.parse_prefix()?;
+
+        loop {
+            let op = match self.peek_binary_op() {
+                Some(op) => op,
+                None => break,
+            };
+
+            let (prec, assoc) = precedence::binary_precedence(&op);
+            if prec < min_prec {
+                break;
+            }
+
+            // Consume the operator token.
+            self.bump();
+
+            let next_min = match assoc {
+                Associativity::Left => prec + 1,
+                Associativity::Right => prec,
+            };
+            let rhs = self.parse_expr(next_min)?;
+            lhs = Expr::BinaryOp(Box::new(lhs), op, Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    /// Parse a prefix form: a unary operator applied recursively, or an atom.
+    fn parse_prefix(&mut self) -> Result<Expr, ParseError> {
+        if let Some(op) = self.peek_unary_op() {
+            self.bump();
+            let operand = self.parse_expr(precedence::unary_precedence())?;
+            return Ok(Expr::UnaryOp(op, Box::new(operand)));
+        }
+
+        self.parse_atom()
+    }
+
+    /// Parse an atom: a literal, a name, or a parenthesized expression.
+    fn parse_atom(&mut self) -> Result<Expr, ParseError> {
+        let tok = match self.bump() {
+            Some(tok) => tok,
+            None => {
+                return Err(ParseError {
+                    kind: ParseErrorKind::UnexpectedEnd,
+                    span: self.end_span(),
+                });
+            }
+        };
+
+        match tok.kind {
+            TokenKind::ParenL => {
+                let inner = self.parse_expr(precedence::min_precedence())?;
+                self.expect(TokenKind::ParenR)?;
+                Ok(inner)
+            }
+            TokenKind::NameLower => Ok(Expr::Name(Name::Lower(tok.span, tok.text.to_owned()))),
+            TokenKind::NameUpper => Ok(Expr::Name(Name::Upper(tok.span, tok.text.to_owned()))),
+            TokenKind::NameUnderscore => Ok(Expr::Name(Name::Underscore(tok.span))),
+            TokenKind::IntBin | TokenKind::IntOct | TokenKind::IntDec | TokenKind::IntHex => {
+                let radix = match tok.kind {
+                    TokenKind::IntBin => Radix::Bin,
+                    TokenKind::IntOct => Radix::Oct,
+                    TokenKind::IntHex => Radix::Hex,
+                    _ => Radix::Dec,
+                };
+                let (value, suffix) = literal::parse_int_literal(&tok, radix).map_err(|_| ParseError {
+                    kind: ParseErrorKind::UnexpectedToken,
+                    span: tok.span.clone(),
+                })?;
+                Ok(Expr::Atom(Atom::Int(tok.span, value, suffix)))
+            }
+            TokenKind::Float => {
+                let (value, suffix) = literal::parse_float_literal(&tok).map_err(|_| ParseError {
+                    kind: ParseErrorKind::UnexpectedToken,
+                    span: tok.span.clone(),
+                })?;
+                Ok(Expr::Atom(Atom::Float(tok.span, value, suffix)))
+            }
+            TokenKind::StringSingle => Ok(Expr::Atom(Atom::StringSingle(
+                tok.span,
+                tok.text.to_owned(),
+            ))),
+            TokenKind::StringMulti => Ok(Expr::Atom(Atom::StringMulti(
+                tok.span,
+                tok.text.to_owned(),
+            ))),
+            TokenKind::StringSmart => {
+                // `tok.text` includes the surrounding quotes.
+                let inner_text = if tok.text.len() >= 2 {
+                    &tok.text[1..tok.text.len() - 1]
+                } else {
+                    ""
+                };
+                let base_offset = tok.span.start + 1;
+                let parts = interp::parse_smart_string(inner_text, base_offset)?;
+                match parts.as_slice() {
+                    [StrPart::Literal(_, text)] => {
+                        Ok(Expr::Atom(Atom::StringSmart(tok.span, text.clone())))
+                    }
+                    _ => Ok(Expr::StringInterp(parts)),
+                }
+            }
+            _ => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                span: tok.span,
+            }),
+        }
+    }
+
+    /// Consume the next significant token if it is `kind`, otherwise return an error.
+    fn expect(&mut self, kind: TokenKind) -> Result<Token<'a>, ParseError> {
+        match self.peek() {
+            Some(tok) if tok.kind == kind => Ok(self.bump().unwrap()),
+            Some(tok) => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                span: tok.span.clone(),
+            }),
+            None => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedEnd,
+                span: self.end_span(),
+            }),
+        }
+    }
+
+    /// Look at the next significant token without consuming it, and interpret it as a
+    /// [`BinaryOp`] if it is one.
+    fn peek_binary_op(&mut self) -> Option<BinaryOp> {
+        let tok = self.peek()?;
+        let span = tok.span.clone();
+        let op = match tok.kind {
+            TokenKind::Amp => BinaryOp::Amp(span),
+            TokenKind::AmpAmp => BinaryOp::AmpAmp(span),
+            TokenKind::BangEq => BinaryOp::BangEq(span),
+            TokenKind::Caret => BinaryOp::Caret(span),
+            TokenKind::ColonColon => BinaryOp::ColonColon(span),
+            TokenKind::Dot => BinaryOp::Dot(span),
+            TokenKind::Eq => BinaryOp::Eq(span),
+            TokenKind::EqEq => BinaryOp::EqEq(span),
+            TokenKind::Gt => BinaryOp::Gt(span),
+            TokenKind::GtGt => BinaryOp::GtGt(span),
+            TokenKind::GtEq => BinaryOp::GtEq(span),
+            TokenKind::Lt => BinaryOp::Lt(span),
+            TokenKind::LtLt => BinaryOp::LtLt(span),
+            TokenKind::LtEq => BinaryOp::LtEq(span),
+            TokenKind::Minus => BinaryOp::Minus(span),
+            TokenKind::Percent => BinaryOp::Percent(span),
+            TokenKind::Pipe => BinaryOp::Pipe(span),
+            TokenKind::PipePipe => BinaryOp::PipePipe(span),
+            TokenKind::Plus => BinaryOp::Plus(span),
+            TokenKind::QuestionEq => BinaryOp::QuestionEq(span),
+            TokenKind::Slash => BinaryOp::Slash(span),
+            TokenKind::SlashSlash => BinaryOp::SlashSlash(span),
+            TokenKind::Star => BinaryOp::Star(span),
+            TokenKind::StarStar => BinaryOp::StarStar(span),
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Look at the next significant token without consuming it, and interpret it as a
+    /// [`UnaryOp`] if it is one.
+    fn peek_unary_op(&mut self) -> Option<UnaryOp> {
+        let tok = self.peek()?;
+        let span = tok.span.clone();
+        let op = match tok.kind {
+            TokenKind::Bang => UnaryOp::Bang(span),
+            TokenKind::Minus => UnaryOp::Minus(span),
+            TokenKind::Star => UnaryOp::Star(span),
+            TokenKind::StarStar => UnaryOp::StarStar(span),
+            TokenKind::Tilde => UnaryOp::Tilde(span),
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Skip past any trivia tokens (space, newlines, comments) starting at `pos`.
+    fn skip_trivia(&mut self) {
+        while let Some(tok) = self.tokens.get(self.pos) {
+            match tok.kind {
+                TokenKind::Space
+                | TokenKind::NewLine
+                | TokenKind::CommentLine
+                | TokenKind::CommentBlock => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Look at the next significant token without consuming it.
+    fn peek(&mut self) -> Option<&Token<'a>> {
+        self.skip_trivia();
+        self.tokens.get(self.pos)
+    }
+
+    /// Consume and return the next significant token.
+    fn bump(&mut self) -> Option<Token<'a>> {
+        self.skip_trivia();
+        let tok = self.tokens.get(self.pos).cloned();
+        if tok.is_some() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    /// Returns the span to use when the token stream ends unexpectedly.
+    fn end_span(&self) -> Span {
+        match self.tokens.last() {
+            Some(tok) => Span::new(tok.span.end, tok.span.end),
+            None => Span::new(0, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::span::Span;
+
+    fn tok(text: &str, kind: TokenKind, start: usize, end: usize) -> Token<'_> {
+        Token::new(text, kind, Span::new(start, end))
+    }
+
+    #[test]
+    fn test_parse_single_atom() {
+        let tokens = [tok("x", TokenKind::NameLower, 0, 1)];
+        let expr = Parser::new(&tokens).parse().unwrap();
+        assert_eq!(expr, Expr::Name(Name::Lower(Span::new(0, 1), "x".into())));
+    }
+
+    #[test]
+    fn test_parse_respects_left_associativity() {
+        // a - b - c => (a - b) - c
+        let tokens = [
+            tok("a", TokenKind::NameLower, 0, 1),
+            tok("-", TokenKind::Minus, 1, 2),
+            tok("b", TokenKind::NameLower, 2, 3),
+            tok("-", TokenKind::Minus, 3, 4),
+            tok("c", TokenKind::NameLower, 4, 5),
+        ];
+        let expr = Parser::new(&tokens).parse().unwrap();
+        let a = Expr::Name(Name::Lower(Span::new(0, 1), "a".into()));
+        let b = Expr::Name(Name::Lower(Span::new(2, 3), "b".into()));
+        let c = Expr::Name(Name::Lower(Span::new(4, 5), "c".into()));
+        let expected = Expr::BinaryOp(
+            Box::new(Expr::BinaryOp(
+                Box::new(a),
+                BinaryOp::Minus(Span::new(1, 2)),
+                Box::new(b),
+            )),
+            BinaryOp::Minus(Span::new(3, 4)),
+            Box::new(c),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_parse_respects_right_associativity() {
+        // a ** b ** c => a ** (b ** c)
+        let tokens = [
+            tok("a", TokenKind::NameLower, 0, 1),
+            tok("**", TokenKind::StarStar, 1, 3),
+            tok("b", TokenKind::NameLower, 3, 4),
+            tok("**", TokenKind::StarStar, 4, 6),
+            tok("c", TokenKind::NameLower, 6, 7),
+        ];
+        let expr = Parser::new(&tokens).parse().unwrap();
+        let a = Expr::Name(Name::Lower(Span::new(0, 1), "a".into()));
+        let b = Expr::Name(Name::Lower(Span::new(3, 4), "b".into()));
+        let c = Expr::Name(Name::Lower(Span::new(6, 7), "c".into()));
+        let expected = Expr::BinaryOp(
+            Box::new(a),
+            BinaryOp::StarStar(Span::new(1, 3)),
+            Box::new(Expr::BinaryOp(
+                Box::new(b),
+                BinaryOp::StarStar(Span::new(4, 6)),
+                Box::new(c),
+            )),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_parse_precedence_of_mul_over_add() {
+        // a + b * c => a + (b * c)
+        let tokens = [
+            tok("a", TokenKind::NameLower, 0, 1),
+            tok("+", TokenKind::Plus, 1, 2),
+            tok("b", TokenKind::NameLower, 2, 3),
+            tok("*", TokenKind::Star, 3, 4),
+            tok("c", TokenKind::NameLower, 4, 5),
+        ];
+        let expr = Parser::new(&tokens).parse().unwrap();
+        match expr {
+            Expr::BinaryOp(_, BinaryOp::Plus(_), rhs) => {
+                assert!(matches!(*rhs, Expr::BinaryOp(_, BinaryOp::Star(_), _)));
+            }
+            _ => panic!("expected a top-level `+`"),
+        }
+    }
+
+    #[test]
+    fn test_parse_star_star_binds_tighter_than_unary() {
+        // -a ** b => -(a ** b)
+        let tokens = [
+            tok("-", TokenKind::Minus, 0, 1),
+            tok("a", TokenKind::NameLower, 1, 2),
+            tok("**", TokenKind::StarStar, 2, 4),
+            tok("b", TokenKind::NameLower, 4, 5),
+        ];
+        let expr = Parser::new(&tokens).parse().unwrap();
+        match expr {
+            Expr::UnaryOp(UnaryOp::Minus(_), operand) => {
+                assert!(matches!(*operand, Expr::BinaryOp(_, BinaryOp::StarStar(_), _)));
+            }
+            _ => panic!("expected a top-level unary `-`"),
+        }
+    }
+
+    #[test]
+    fn test_parse_parenthesized_group() {
+        // (a + b) * c => (a + b) * c, grouped explicitly by the parens
+        let tokens = [
+            tok("(", TokenKind::ParenL, 0, 1),
+            tok("a", TokenKind::NameLower, 1, 2),
+            tok("+", TokenKind::Plus, 2, 3),
+            tok("b", TokenKind::NameLower, 3, 4),
+            tok(")", TokenKind::ParenR, 4, 5),
+            tok("*", TokenKind::Star, 5, 6),
+            tok("c", TokenKind::NameLower, 6, 7),
+        ];
+        let expr = Parser::new(&tokens).parse().unwrap();
+        match expr {
+            Expr::BinaryOp(lhs, BinaryOp::Star(_), _) => {
+                assert!(matches!(*lhs, Expr::BinaryOp(_, BinaryOp::Plus(_), _)));
+            }
+            _ => panic!("expected a top-level `*`"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unexpected_end_is_an_error() {
+        let tokens: [Token; 0] = [];
+        let err = Parser::new(&tokens).parse().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEnd);
+    }
+}
@@ -0,0 +1,126 @@
+//! The precedence and associativity of operators, which drive expression parsing.
+
+use super::ast::BinaryOp;
+
+/// The associativity of an operator, which determines how a chain of operators at the same
+/// precedence is grouped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Associativity {
+    /// The operator groups from the left, e.g. `a - b - c` is `(a - b) - c`.
+    Left,
+
+    /// The operator groups from the right, e.g. `a = b = c` is `a = (b = c)`.
+    Right,
+}
+
+/// The precedence used when parsing the operand of a unary operator. It sits between the power
+/// operator and the multiplicative operators, so `-a ** b` is `-(a ** b)` but `-a * b` is
+/// `(-a) * b`.
+const UNARY_PRECEDENCE: u8 = 85;
+
+/// Returns the sentinel precedence used to start a top-level parse. It is lower than the
+/// precedence of every operator in the table, so every operator is accepted.
+///
+/// # Returns
+///
+/// The minimum precedence.
+pub fn min_precedence() -> u8 {
+    0
+}
+
+/// Returns the precedence used when parsing the operand of a unary operator.
+///
+/// # Returns
+///
+/// The precedence of a unary operand.
+pub fn unary_precedence() -> u8 {
+    UNARY_PRECEDENCE
+}
+
+/// Returns the precedence and associativity of a [`BinaryOp`]. This table is the single source
+/// of truth for operator grouping, from the tightest-binding operators (`.` and `::`) to the
+/// loosest (`=` and `?=`).
+///
+/// # Arguments
+///
+/// * `op` - The operator to look up.
+///
+/// # Returns
+///
+/// A tuple of the precedence and the associativity of `op`.
+pub fn binary_precedence(op: &BinaryOp) -> (u8, Associativity) {
+    use Associativity::*;
+    use BinaryOp::*;
+
+    match op {
+        Dot(_) | ColonColon(_) => (100, Left),
+        StarStar(_) => (90, Right),
+        Star(_) | Slash(_) | SlashSlash(_) | Percent(_) => (80, Left),
+        Plus(_) | Minus(_) => (70, Left),
+        LtLt(_) | GtGt(_) => (60, Left),
+        Amp(_) => (50, Left),
+        Caret(_) => (45, Left),
+        Pipe(_) => (40, Left),
+        Lt(_) | LtEq(_) | Gt(_) | GtEq(_) | EqEq(_) | BangEq(_) => (30, Left),
+        AmpAmp(_) => (20, Left),
+        PipePipe(_) => (10, Left),
+        QuestionEq(_) | Eq(_) => (5, Right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::span::Span;
+
+    fn span() -> Span {
+        Span::new(0, 1)
+    }
+
+    #[test]
+    fn test_min_precedence_below_every_operator() {
+        let ops = [
+            BinaryOp::Dot(span()),
+            BinaryOp::ColonColon(span()),
+            BinaryOp::StarStar(span()),
+            BinaryOp::Star(span()),
+            BinaryOp::Plus(span()),
+            BinaryOp::PipePipe(span()),
+            BinaryOp::Eq(span()),
+            BinaryOp::QuestionEq(span()),
+        ];
+        for op in ops.iter() {
+            let (prec, _) = binary_precedence(op);
+            assert!(prec > min_precedence());
+        }
+    }
+
+    #[test]
+    fn test_dot_binds_tighter_than_star_star() {
+        let (dot_prec, _) = binary_precedence(&BinaryOp::Dot(span()));
+        let (pow_prec, _) = binary_precedence(&BinaryOp::StarStar(span()));
+        assert!(dot_prec > pow_prec);
+    }
+
+    #[test]
+    fn test_star_star_is_right_associative() {
+        let (_, assoc) = binary_precedence(&BinaryOp::StarStar(span()));
+        assert_eq!(assoc, Associativity::Right);
+    }
+
+    #[test]
+    fn test_eq_is_right_associative_and_loosest() {
+        let (eq_prec, assoc) = binary_precedence(&BinaryOp::Eq(span()));
+        assert_eq!(assoc, Associativity::Right);
+        let (or_prec, _) = binary_precedence(&BinaryOp::PipePipe(span()));
+        assert!(eq_prec < or_prec);
+    }
+
+    #[test]
+    fn test_unary_precedence_between_star_star_and_star() {
+        let (pow_prec, _) = binary_precedence(&BinaryOp::StarStar(span()));
+        let (mul_prec, _) = binary_precedence(&BinaryOp::Star(span()));
+        assert!(unary_precedence() < pow_prec);
+        assert!(unary_precedence() > mul_prec);
+    }
+}
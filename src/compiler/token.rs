@@ -185,10 +185,17 @@ pub struct Token<'a> {
 
     /// The location of the token.
     pub span: Span,
+
+    /// A type suffix lexed immediately after the digits of a numeric literal, e.g. the `u8` in
+    /// `255u8`. Only ever set on `IntBin`/`IntOct`/`IntDec`/`IntHex`/`Float` tokens.
+    ///
+    /// No lexer populates this yet (see the module doc on [`super::literal`]); it is built by
+    /// hand via [`Token::with_suffix`] until edition 1 lexing exists.
+    pub suffix: Option<&'a str>,
 }
 
 impl<'a> Token<'a> {
-    /// Create a new [`Token`].
+    /// Create a new [`Token`] with no literal suffix.
     ///
     /// # Arguments
     ///
@@ -204,6 +211,28 @@ impl<'a> Token<'a> {
             text: text,
             kind: kind,
             span: span,
+            suffix: None,
+        }
+    }
+
+    /// Create a new [`Token`] carrying a literal suffix.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the token, not including the suffix.
+    /// * `kind` - The kind of the token.
+    /// * `span` - The location of the token in the source.
+    /// * `suffix` - The type suffix lexed immediately after the digits.
+    ///
+    /// # Returns
+    ///
+    /// A new [`Token`].
+    pub fn with_suffix(text: &'a str, kind: TokenKind, span: Span, suffix: &'a str) -> Token<'a> {
+        Token {
+            text: text,
+            kind: kind,
+            span: span,
+            suffix: Some(suffix),
         }
     }
 }
@@ -220,7 +249,21 @@ mod tests {
             Token {
                 text: "hello",
                 kind: NameLower,
-                span: Span::new(5, 10)
+                span: Span::new(5, 10),
+                suffix: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_token_with_suffix() {
+        assert_eq!(
+            Token::with_suffix("255", IntDec, Span::new(0, 5), "u8"),
+            Token {
+                text: "255",
+                kind: IntDec,
+                span: Span::new(0, 5),
+                suffix: Some("u8"),
             }
         );
     }
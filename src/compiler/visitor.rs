@@ -0,0 +1,476 @@
+//! A visitor for walking, and optionally rewriting, the `Expr` AST. As new `Expr` variants are
+//! added, the `walk_*` functions here are the single place that needs to stay in sync; every
+//! other pass (name resolution, constant folding, pretty-printing, ...) gets an exhaustive
+//! traversal for free by implementing [`Visitor`] or [`VisitorMut`] and overriding only the cases
+//! it cares about.
+
+use super::ast::{Atom, Expr, FuncArg, FuncArgBlock, Name, StrPart, TraitItems, TypeItems};
+
+/// A read-only traversal of the AST. Every method has a default implementation that recurses into
+/// the node's children via the matching `walk_*` function, except `visit_name`, whose default does
+/// nothing since a [`Name`] has no children.
+pub trait Visitor {
+    /// Visit an [`Expr`].
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    /// Visit an [`Atom`].
+    fn visit_atom(&mut self, atom: &Atom) {
+        walk_atom(self, atom);
+    }
+
+    /// Visit a [`Name`].
+    fn visit_name(&mut self, _name: &Name) {}
+
+    /// Visit a [`FuncArg`].
+    fn visit_func_arg(&mut self, arg: &FuncArg) {
+        walk_func_arg(self, arg);
+    }
+
+    /// Visit a [`FuncArgBlock`].
+    fn visit_func_arg_block(&mut self, block: &FuncArgBlock) {
+        walk_func_arg_block(self, block);
+    }
+
+    /// Visit a [`TypeItems`].
+    fn visit_type_items(&mut self, items: &TypeItems) {
+        walk_type_items(self, items);
+    }
+
+    /// Visit a [`TraitItems`].
+    fn visit_trait_items(&mut self, items: &TraitItems) {
+        walk_trait_items(self, items);
+    }
+}
+
+/// Recurse into the children of an [`Expr`], dispatching each to the visitor.
+///
+/// # Arguments
+///
+/// * `visitor` - The visitor to dispatch to.
+/// * `expr` - The expression to walk.
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Atom(atom) => visitor.visit_atom(atom),
+        Expr::StringInterp(parts) => {
+            for part in parts {
+                if let StrPart::Expr(_, e) = part {
+                    visitor.visit_expr(e);
+                }
+            }
+        }
+        Expr::Name(name) => visitor.visit_name(name),
+        Expr::Block(exprs) => {
+            for e in exprs {
+                visitor.visit_expr(e);
+            }
+        }
+        Expr::FunctionCall(func, args, block) => {
+            visitor.visit_expr(func);
+            for arg in args {
+                visitor.visit_func_arg(arg);
+            }
+            visitor.visit_func_arg_block(block);
+        }
+        Expr::BinaryOp(lhs, _op, rhs) => {
+            visitor.visit_expr(lhs);
+            visitor.visit_expr(rhs);
+        }
+        Expr::UnaryOp(_op, operand) => visitor.visit_expr(operand),
+        Expr::If(cond, then_branch, else_branch) => {
+            visitor.visit_expr(cond);
+            visitor.visit_expr(then_branch);
+            if let Some(else_branch) = else_branch {
+                visitor.visit_expr(else_branch);
+            }
+        }
+        Expr::Loop(body) => visitor.visit_expr(body),
+        Expr::For(var, seq, body) => {
+            visitor.visit_expr(var);
+            visitor.visit_expr(seq);
+            visitor.visit_expr(body);
+        }
+        Expr::While(cond, body) => {
+            visitor.visit_expr(cond);
+            visitor.visit_expr(body);
+        }
+        Expr::Type(name, traits, items) => {
+            visitor.visit_expr(name);
+            for t in traits {
+                visitor.visit_expr(t);
+            }
+            for item in items {
+                visitor.visit_type_items(item);
+            }
+        }
+        Expr::Trait(name, traits, items) => {
+            visitor.visit_expr(name);
+            for t in traits {
+                visitor.visit_expr(t);
+            }
+            for item in items {
+                visitor.visit_trait_items(item);
+            }
+        }
+        Expr::Use(e) | Expr::Pub(e) => visitor.visit_expr(e),
+        Expr::Break(e) | Expr::Continue(e) | Expr::Return(e) => {
+            if let Some(e) = e {
+                visitor.visit_expr(e);
+            }
+        }
+    }
+}
+
+/// Recurse into the children of an [`Atom`]. Atoms are always leaves, so this does nothing.
+///
+/// # Arguments
+///
+/// * `visitor` - The visitor to dispatch to.
+/// * `atom` - The atom to walk.
+pub fn walk_atom<V: Visitor + ?Sized>(_visitor: &mut V, _atom: &Atom) {}
+
+/// Recurse into the children of a [`FuncArg`].
+///
+/// # Arguments
+///
+/// * `visitor` - The visitor to dispatch to.
+/// * `arg` - The argument to walk.
+pub fn walk_func_arg<V: Visitor + ?Sized>(visitor: &mut V, arg: &FuncArg) {
+    match arg {
+        FuncArg::Positional(e) => visitor.visit_expr(e),
+        FuncArg::Variadic(exprs) => {
+            for e in exprs {
+                visitor.visit_expr(e);
+            }
+        }
+        FuncArg::Keyword(key, value) => {
+            visitor.visit_expr(key);
+            visitor.visit_expr(value);
+        }
+        FuncArg::Keywords(e) => visitor.visit_expr(e),
+        FuncArg::BlockArg(e) => visitor.visit_expr(e),
+        FuncArg::BlockFunc(block) => visitor.visit_func_arg_block(block),
+    }
+}
+
+/// Recurse into the children of a [`FuncArgBlock`].
+///
+/// # Arguments
+///
+/// * `visitor` - The visitor to dispatch to.
+/// * `block` - The block to walk.
+pub fn walk_func_arg_block<V: Visitor + ?Sized>(visitor: &mut V, block: &FuncArgBlock) {
+    if let Some(args) = &block.args {
+        for arg in args {
+            visitor.visit_func_arg(arg);
+        }
+    }
+    visitor.visit_expr(&block.expr);
+}
+
+/// Recurse into the children of a [`TypeItems`].
+///
+/// # Arguments
+///
+/// * `visitor` - The visitor to dispatch to.
+/// * `items` - The item to walk.
+pub fn walk_type_items<V: Visitor + ?Sized>(visitor: &mut V, items: &TypeItems) {
+    match items {
+        TypeItems::Cons(_, name, args, body)
+        | TypeItems::Method(_, name, args, body)
+        | TypeItems::MethodStatic(_, name, args, body) => {
+            visitor.visit_expr(name);
+            for arg in args {
+                visitor.visit_func_arg(arg);
+            }
+            visitor.visit_expr(body);
+        }
+        TypeItems::Property(_, name, body) | TypeItems::PropertyStatic(_, name, body) => {
+            visitor.visit_expr(name);
+            visitor.visit_expr(body);
+        }
+        TypeItems::BinaryOp(_, lhs, _op, rhs, body) => {
+            visitor.visit_expr(lhs);
+            visitor.visit_expr(rhs);
+            visitor.visit_expr(body);
+        }
+        TypeItems::UnaryOp(_, _op, operand) => visitor.visit_expr(operand),
+    }
+}
+
+/// Recurse into the children of a [`TraitItems`].
+///
+/// # Arguments
+///
+/// * `visitor` - The visitor to dispatch to.
+/// * `items` - The item to walk.
+pub fn walk_trait_items<V: Visitor + ?Sized>(visitor: &mut V, items: &TraitItems) {
+    match items {
+        TraitItems::Cons(body) => visitor.visit_expr(body),
+        TraitItems::Method(_, name, args, body) => {
+            visitor.visit_expr(name);
+            for arg in args {
+                visitor.visit_func_arg(arg);
+            }
+            visitor.visit_expr(body);
+        }
+        TraitItems::BinaryOp(_, lhs, _op, rhs, body) => {
+            visitor.visit_expr(lhs);
+            visitor.visit_expr(rhs);
+            visitor.visit_expr(body);
+        }
+        TraitItems::UnaryOp(_, _op, operand) => visitor.visit_expr(operand),
+    }
+}
+
+/// A mutable traversal of the AST, allowing in-place rewriting of boxed subexpressions. Every
+/// method has a default implementation that recurses into the node's children via the matching
+/// `walk_*_mut` function, except `visit_name_mut`, whose default does nothing since a [`Name`]
+/// has no children.
+pub trait VisitorMut {
+    /// Visit and possibly rewrite an [`Expr`].
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    /// Visit and possibly rewrite an [`Atom`].
+    fn visit_atom_mut(&mut self, atom: &mut Atom) {
+        walk_atom_mut(self, atom);
+    }
+
+    /// Visit and possibly rewrite a [`Name`].
+    fn visit_name_mut(&mut self, _name: &mut Name) {}
+
+    /// Visit and possibly rewrite a [`FuncArg`].
+    fn visit_func_arg_mut(&mut self, arg: &mut FuncArg) {
+        walk_func_arg_mut(self, arg);
+    }
+
+    /// Visit and possibly rewrite a [`FuncArgBlock`].
+    fn visit_func_arg_block_mut(&mut self, block: &mut FuncArgBlock) {
+        walk_func_arg_block_mut(self, block);
+    }
+
+    /// Visit and possibly rewrite a [`TypeItems`].
+    fn visit_type_items_mut(&mut self, items: &mut TypeItems) {
+        walk_type_items_mut(self, items);
+    }
+
+    /// Visit and possibly rewrite a [`TraitItems`].
+    fn visit_trait_items_mut(&mut self, items: &mut TraitItems) {
+        walk_trait_items_mut(self, items);
+    }
+}
+
+/// Recurse into, and allow rewriting, the children of an [`Expr`].
+///
+/// # Arguments
+///
+/// * `visitor` - The visitor to dispatch to.
+/// * `expr` - The expression to walk.
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Atom(atom) => visitor.visit_atom_mut(atom),
+        Expr::StringInterp(parts) => {
+            for part in parts {
+                if let StrPart::Expr(_, e) = part {
+                    visitor.visit_expr_mut(e);
+                }
+            }
+        }
+        Expr::Name(name) => visitor.visit_name_mut(name),
+        Expr::Block(exprs) => {
+            for e in exprs {
+                visitor.visit_expr_mut(e);
+            }
+        }
+        Expr::FunctionCall(func, args, block) => {
+            visitor.visit_expr_mut(func);
+            for arg in args {
+                visitor.visit_func_arg_mut(arg);
+            }
+            visitor.visit_func_arg_block_mut(block);
+        }
+        Expr::BinaryOp(lhs, _op, rhs) => {
+            visitor.visit_expr_mut(lhs);
+            visitor.visit_expr_mut(rhs);
+        }
+        Expr::UnaryOp(_op, operand) => visitor.visit_expr_mut(operand),
+        Expr::If(cond, then_branch, else_branch) => {
+            visitor.visit_expr_mut(cond);
+            visitor.visit_expr_mut(then_branch);
+            if let Some(else_branch) = else_branch {
+                visitor.visit_expr_mut(else_branch);
+            }
+        }
+        Expr::Loop(body) => visitor.visit_expr_mut(body),
+        Expr::For(var, seq, body) => {
+            visitor.visit_expr_mut(var);
+            visitor.visit_expr_mut(seq);
+            visitor.visit_expr_mut(body);
+        }
+        Expr::While(cond, body) => {
+            visitor.visit_expr_mut(cond);
+            visitor.visit_expr_mut(body);
+        }
+        Expr::Type(name, traits, items) => {
+            visitor.visit_expr_mut(name);
+            for t in traits {
+                visitor.visit_expr_mut(t);
+            }
+            for item in items {
+                visitor.visit_type_items_mut(item);
+            }
+        }
+        Expr::Trait(name, traits, items) => {
+            visitor.visit_expr_mut(name);
+            for t in traits {
+                visitor.visit_expr_mut(t);
+            }
+            for item in items {
+                visitor.visit_trait_items_mut(item);
+            }
+        }
+        Expr::Use(e) | Expr::Pub(e) => visitor.visit_expr_mut(e),
+        Expr::Break(e) | Expr::Continue(e) | Expr::Return(e) => {
+            if let Some(e) = e {
+                visitor.visit_expr_mut(e);
+            }
+        }
+    }
+}
+
+/// Recurse into the children of an [`Atom`]. Atoms are always leaves, so this does nothing.
+pub fn walk_atom_mut<V: VisitorMut + ?Sized>(_visitor: &mut V, _atom: &mut Atom) {}
+
+/// Recurse into, and allow rewriting, the children of a [`FuncArg`].
+pub fn walk_func_arg_mut<V: VisitorMut + ?Sized>(visitor: &mut V, arg: &mut FuncArg) {
+    match arg {
+        FuncArg::Positional(e) => visitor.visit_expr_mut(e),
+        FuncArg::Variadic(exprs) => {
+            for e in exprs {
+                visitor.visit_expr_mut(e);
+            }
+        }
+        FuncArg::Keyword(key, value) => {
+            visitor.visit_expr_mut(key);
+            visitor.visit_expr_mut(value);
+        }
+        FuncArg::Keywords(e) => visitor.visit_expr_mut(e),
+        FuncArg::BlockArg(e) => visitor.visit_expr_mut(e),
+        FuncArg::BlockFunc(block) => visitor.visit_func_arg_block_mut(block),
+    }
+}
+
+/// Recurse into, and allow rewriting, the children of a [`FuncArgBlock`].
+pub fn walk_func_arg_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, block: &mut FuncArgBlock) {
+    if let Some(args) = &mut block.args {
+        for arg in args {
+            visitor.visit_func_arg_mut(arg);
+        }
+    }
+    visitor.visit_expr_mut(&mut block.expr);
+}
+
+/// Recurse into, and allow rewriting, the children of a [`TypeItems`].
+pub fn walk_type_items_mut<V: VisitorMut + ?Sized>(visitor: &mut V, items: &mut TypeItems) {
+    match items {
+        TypeItems::Cons(_, name, args, body)
+        | TypeItems::Method(_, name, args, body)
+        | TypeItems::MethodStatic(_, name, args, body) => {
+            visitor.visit_expr_mut(name);
+            for arg in args {
+                visitor.visit_func_arg_mut(arg);
+            }
+            visitor.visit_expr_mut(body);
+        }
+        TypeItems::Property(_, name, body) | TypeItems::PropertyStatic(_, name, body) => {
+            visitor.visit_expr_mut(name);
+            visitor.visit_expr_mut(body);
+        }
+        TypeItems::BinaryOp(_, lhs, _op, rhs, body) => {
+            visitor.visit_expr_mut(lhs);
+            visitor.visit_expr_mut(rhs);
+            visitor.visit_expr_mut(body);
+        }
+        TypeItems::UnaryOp(_, _op, operand) => visitor.visit_expr_mut(operand),
+    }
+}
+
+/// Recurse into, and allow rewriting, the children of a [`TraitItems`].
+pub fn walk_trait_items_mut<V: VisitorMut + ?Sized>(visitor: &mut V, items: &mut TraitItems) {
+    match items {
+        TraitItems::Cons(body) => visitor.visit_expr_mut(body),
+        TraitItems::Method(_, name, args, body) => {
+            visitor.visit_expr_mut(name);
+            for arg in args {
+                visitor.visit_func_arg_mut(arg);
+            }
+            visitor.visit_expr_mut(body);
+        }
+        TraitItems::BinaryOp(_, lhs, _op, rhs, body) => {
+            visitor.visit_expr_mut(lhs);
+            visitor.visit_expr_mut(rhs);
+            visitor.visit_expr_mut(body);
+        }
+        TraitItems::UnaryOp(_, _op, operand) => visitor.visit_expr_mut(operand),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ast::{BinaryOp, Name};
+    use crate::compiler::span::Span;
+
+    struct NameCounter {
+        count: usize,
+    }
+
+    impl Visitor for NameCounter {
+        fn visit_name(&mut self, _name: &Name) {
+            self.count += 1;
+        }
+    }
+
+    #[test]
+    fn test_visitor_counts_nested_names() {
+        let span = Span::new(0, 1);
+        let expr = Expr::BinaryOp(
+            Box::new(Expr::Name(Name::Lower(span.clone(), "a".into()))),
+            BinaryOp::Plus(span.clone()),
+            Box::new(Expr::Name(Name::Lower(span.clone(), "b".into()))),
+        );
+        let mut counter = NameCounter { count: 0 };
+        counter.visit_expr(&expr);
+        assert_eq!(counter.count, 2);
+    }
+
+    struct Renamer;
+
+    impl VisitorMut for Renamer {
+        fn visit_name_mut(&mut self, name: &mut Name) {
+            if let Name::Lower(_, text) = name {
+                text.push_str("_renamed");
+            }
+        }
+    }
+
+    #[test]
+    fn test_visitor_mut_rewrites_nested_names() {
+        let span = Span::new(0, 1);
+        let mut expr = Expr::UnaryOp(
+            crate::compiler::ast::UnaryOp::Minus(span.clone()),
+            Box::new(Expr::Name(Name::Lower(span, "a".into()))),
+        );
+        Renamer.visit_expr_mut(&mut expr);
+        match expr {
+            Expr::UnaryOp(_, operand) => match *operand {
+                Expr::Name(Name::Lower(_, text)) => assert_eq!(text, "a_renamed"),
+                _ => panic!("expected a name"),
+            },
+            _ => panic!("expected a unary op"),
+        }
+    }
+}
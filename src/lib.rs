@@ -7,3 +7,4 @@ pub static VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
 pub mod compiler;
 pub mod edition;
+pub mod util;
@@ -0,0 +1,4 @@
+//! Generic utilities shared across the compiler.
+
+pub mod macros;
+pub mod text;
@@ -1,31 +1,90 @@
 //! Utility functions for working with text.
 
-/// Test that a string contains an ASCII lowercase character.
+use unicode_xid::UnicodeXID;
+
+/// The derived case of a name, used to decide between `Name::Lower` and `Name::Upper`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NameCase {
+    /// The name's first cased character is lowercase.
+    Lower,
+
+    /// The name's first cased character is uppercase.
+    Upper,
+}
+
+/// Test that a string's first scalar is a lowercase letter, per the Unicode `Lowercase`
+/// property.
 pub fn is_lower(s: &str) -> bool {
-    match s {
-        "a" | "b" | "c" | "d" | "e" | "f" | "g" | "h" | "i" | "j" | "k" | "l" | "m" | "n" | "o"
-        | "p" | "q" | "r" | "s" | "t" | "u" | "v" | "w" | "x" | "y" | "z" => true,
-        _ => false,
-    }
+    s.chars().next().is_some_and(|c| c.is_lowercase())
 }
 
-/// Test that a string contains an ASCII uppercase character.
+/// Test that a string's first scalar is an uppercase letter, per the Unicode `Uppercase`
+/// property.
 pub fn is_upper(s: &str) -> bool {
-    match s {
-        "A" | "B" | "C" | "D" | "E" | "F" | "G" | "H" | "I" | "J" | "K" | "L" | "M" | "N" | "O"
-        | "P" | "Q" | "R" | "S" | "T" | "U" | "V" | "W" | "X" | "Y" | "Z" => true,
-        _ => false,
+    s.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+/// Test that a string's first scalar may start an identifier: an underscore, or a scalar with
+/// the Unicode `XID_Start` property.
+pub fn is_id_start(s: &str) -> bool {
+    match s.chars().next() {
+        Some('_') => true,
+        Some(c) => c.is_xid_start(),
+        None => false,
+    }
+}
+
+/// Test that a string's first scalar may continue an identifier, per the Unicode `XID_Continue`
+/// property. `XID_Continue` already includes underscore and decimal digits.
+pub fn is_id_continue(s: &str) -> bool {
+    match s.chars().next() {
+        Some(c) => c.is_xid_continue(),
+        None => false,
     }
 }
 
-/// Test that a string contains an ASCII alphabetic character or underscore.
+/// Test that a string's first scalar may start an identifier. This is an alias for
+/// [`is_id_start`], kept for callers that think in terms of "alphabetic".
 pub fn is_alpha(s: &str) -> bool {
-    is_lower(s) || is_upper(s) || s == "_"
+    is_id_start(s)
 }
 
-/// Test that a string contains an ASCII alphabetic character, underscore, or decimal digit.
+/// Test that a string's first scalar may continue an identifier. This is an alias for
+/// [`is_id_continue`], kept for callers that think in terms of "alphanumeric".
 pub fn is_alphanum(s: &str) -> bool {
-    is_alpha(s) || is_digit(s)
+    is_id_continue(s)
+}
+
+/// Determine the case of a name by inspecting its first non-underscore character. This is what
+/// distinguishes `Name::Lower` from `Name::Upper`, so that names like `_Foo` are still classified
+/// by the letter that follows the underscore.
+///
+/// # Arguments
+///
+/// * `s` - The name to classify.
+///
+/// # Returns
+///
+/// `Some(NameCase::Lower)` or `Some(NameCase::Upper)` for the case of the first non-underscore
+/// character, or `None` if the name is entirely underscores or its first non-underscore
+/// character has no case (for example a digit or a caseless letter). Characters after that one
+/// are not looked at.
+pub fn name_case(s: &str) -> Option<NameCase> {
+    for c in s.chars() {
+        if c == '_' {
+            continue;
+        }
+        if c.is_lowercase() {
+            return Some(NameCase::Lower);
+        }
+        if c.is_uppercase() {
+            return Some(NameCase::Upper);
+        }
+        // The first non-underscore character has no case (e.g. a digit, or a caseless letter
+        // such as those in CJK scripts), so there is nothing further to look at.
+        break;
+    }
+    None
 }
 
 /// Test that a string contains an ASCII decimal digit.
@@ -64,6 +123,7 @@ pub fn is_hex_digit(s: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use unicode_segmentation::UnicodeSegmentation;
 
     static LOWER: &'static str = "abcdefghijklmnopqrstuvwxyz";
     static UPPER: &'static str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
@@ -183,4 +243,38 @@ mod tests {
             assert!(!is_hex_digit(g));
         }
     }
+
+    #[test]
+    fn test_is_alpha_accepts_non_ascii_identifiers() {
+        assert!(is_alpha("é"));
+        assert!(is_alpha("Ж"));
+        assert!(is_alpha("字"));
+    }
+
+    #[test]
+    fn test_is_alpha_rejects_combining_marks_as_a_start() {
+        // A combining mark may continue an identifier but not start one.
+        assert!(!is_alpha("\u{0301}"));
+        assert!(is_alphanum("\u{0301}"));
+    }
+
+    #[test]
+    fn test_name_case_skips_leading_underscores() {
+        assert_eq!(name_case("_Foo"), Some(NameCase::Upper));
+        assert_eq!(name_case("__bar"), Some(NameCase::Lower));
+        assert_eq!(name_case("foo"), Some(NameCase::Lower));
+        assert_eq!(name_case("Foo"), Some(NameCase::Upper));
+    }
+
+    #[test]
+    fn test_name_case_is_none_without_a_cased_character() {
+        assert_eq!(name_case("___"), None);
+        assert_eq!(name_case("_123"), None);
+    }
+
+    #[test]
+    fn test_name_case_follows_unicode_case_not_ascii() {
+        assert_eq!(name_case("Жук"), Some(NameCase::Upper));
+        assert_eq!(name_case("ёжик"), Some(NameCase::Lower));
+    }
 }